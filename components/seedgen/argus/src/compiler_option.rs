@@ -0,0 +1,17 @@
+/// A single command-line option forwarded to the wrapped compiler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompilerOption {
+    value: String,
+}
+
+impl CompilerOption {
+    pub fn new(value: &str) -> Self {
+        CompilerOption {
+            value: value.to_string(),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt::Write as _;
+
+use crate::env::ERROR_FORMAT;
+
+/// Severity of a [`Diagnostic`].
+#[derive(Debug, Clone, Copy)]
+pub enum Level {
+    Error,
+    Warning,
+}
+
+impl Level {
+    fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+        }
+    }
+}
+
+/// A structured diagnostic.
+///
+/// The free-form `message` drives the human backend; the optional context
+/// fields are what the JSON backend exposes under `spans` so a driving harness
+/// can key off them instead of scraping text.
+#[derive(Debug, Default)]
+pub struct Diagnostic {
+    level: Option<Level>,
+    message: String,
+    code: Option<&'static str>,
+    env_var: Option<String>,
+    path: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Level::Error, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Level::Warning, message)
+    }
+
+    fn new(level: Level, message: impl Into<String>) -> Self {
+        Diagnostic {
+            level: Some(level),
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    pub fn env_var(mut self, env_var: impl Into<String>) -> Self {
+        self.env_var = Some(env_var.into());
+        self
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    fn level(&self) -> Level {
+        self.level.unwrap_or(Level::Error)
+    }
+
+    /// Emits this diagnostic through the selected backend.
+    pub fn emit(self) {
+        match std::env::var(ERROR_FORMAT).as_deref() {
+            Ok("json") => self.emit_json(),
+            _ => self.emit_human(),
+        }
+    }
+
+    /// Reproduces the wrapper's existing free-form stderr output.
+    fn emit_human(&self) {
+        eprintln!("{}", self.message);
+    }
+
+    /// Emits one JSON object per line: `{level, message, code, spans:{...}}`.
+    fn emit_json(&self) {
+        let mut spans = String::new();
+        let mut push = |key: &str, value: &str| {
+            if !spans.is_empty() {
+                spans.push(',');
+            }
+            let _ = write!(spans, "\"{}\":{}", key, json_string(value));
+        };
+        if let Some(env_var) = &self.env_var {
+            push("env_var", env_var);
+        }
+        if let Some(path) = &self.path {
+            push("path", path);
+        }
+
+        let code = self
+            .code
+            .map(json_string)
+            .unwrap_or_else(|| String::from("null"));
+        eprintln!(
+            "{{\"level\":{},\"message\":{},\"code\":{},\"spans\":{{{}}}}}",
+            json_string(self.level().as_str()),
+            json_string(&self.message),
+            code,
+            spans
+        );
+    }
+}
+
+/// Escapes a string into a JSON string literal (quotes included).
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
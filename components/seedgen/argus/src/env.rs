@@ -37,6 +37,8 @@ define_env_vars! {
     NOSANITIZER: "BANDFUZZ_NOSAN" => "Disable all sanitizers.",
     OPT_LEVEL: "BANDFUZZ_OPT" => "Optimization level for the target.",
     COMPILATION_DATABASE_DIR: "COMPILATION_DATABASE_DIR" => "Directory to write the compilation database to.",
+    ICE_FILE: "ARGUS_ICE_FILE" => "Path to write a crash report to on abnormal termination.",
+    ERROR_FORMAT: "ARGUS_ERROR_FORMAT" => "Diagnostic output format (json for machine-readable records).",
 }
 
 pub fn print_envs() {
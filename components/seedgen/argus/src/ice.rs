@@ -0,0 +1,60 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::panic;
+
+use crate::env::{ARGUS_ENVS, ICE_FILE};
+use crate::sanitizer::SanitizerSet;
+
+/// Resolves the crash-report path, defaulting to `/tmp/argus-ice-<pid>.txt`.
+fn ice_path() -> String {
+    std::env::var(ICE_FILE).unwrap_or_else(|_| format!("/tmp/argus-ice-{}.txt", std::process::id()))
+}
+
+/// Renders a self-contained crash report: the resolved `ARGUS_ENVS` table, the
+/// argv handed to the wrapped compiler, the active [`SanitizerSet`], and a
+/// captured backtrace.
+fn render_report(compiler_argv: &[String]) -> String {
+    let mut report = String::new();
+    let _ = writeln!(report, "Argus crash report (pid {})", std::process::id());
+
+    let _ = writeln!(report, "\n== environment ==");
+    for (env, _description) in ARGUS_ENVS.iter() {
+        let value = std::env::var(env).unwrap_or_else(|_| String::from("-"));
+        let _ = writeln!(report, "{} = {}", env, value);
+    }
+
+    let _ = writeln!(report, "\n== compiler argv ==");
+    let _ = writeln!(report, "{}", compiler_argv.join(" "));
+
+    let _ = writeln!(report, "\n== sanitizers ==");
+    let _ = writeln!(report, "{:?}", SanitizerSet::from_env());
+
+    let _ = writeln!(report, "\n== backtrace ==");
+    let _ = writeln!(report, "{:?}", backtrace::Backtrace::new());
+
+    report
+}
+
+/// Writes a crash report to the configured path, reporting success or failure
+/// on stderr. Used both from the panic hook and when the wrapped compiler
+/// itself terminates abnormally.
+pub fn report(compiler_argv: &[String]) {
+    let path = ice_path();
+    match fs::write(&path, render_report(compiler_argv)) {
+        Ok(()) => eprintln!("Argus crash report written to {}", path),
+        Err(e) => eprintln!("Failed to write crash report to {}: {}", path, e),
+    }
+}
+
+/// Installs a panic hook that writes an ICE-style crash report alongside the
+/// default panic output, so a user can attach a single artifact that fully
+/// reproduces the wrapper's decision state.
+///
+/// `compiler_argv` is recorded as given in every report the hook writes.
+pub fn install(compiler_argv: Vec<String>) {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        report(&compiler_argv);
+        default_hook(info);
+    }));
+}
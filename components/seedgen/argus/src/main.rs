@@ -0,0 +1,65 @@
+use std::process::Command;
+
+mod compiler_option;
+mod emitter;
+mod env;
+mod ice;
+mod option_visitors;
+mod profiler;
+mod sanitizer;
+
+use compiler_option::CompilerOption;
+use option_visitors::compilation_database_visitor::CompilationDatabaseVisitor;
+use option_visitors::sanitizer_visitor::SanitizerVisitor;
+use option_visitors::{apply_visitors, OptionVisitor};
+
+fn main() {
+    let mut argv: Vec<String> = std::env::args().skip(1).collect();
+
+    // With no arguments, behave as a help stub listing the env vars we honour.
+    if argv.is_empty() {
+        env::print_envs();
+        return;
+    }
+
+    // The first token is the compiler to wrap, the rest are its options.
+    let compiler = argv.remove(0);
+
+    // Install the crash-report hook before the visitors run. It captures the
+    // wrapper's own argv, so a panic report shows the options before rewriting.
+    let mut compiler_argv = vec![compiler.clone()];
+    compiler_argv.extend(argv.iter().cloned());
+    ice::install(compiler_argv.clone());
+
+    let mut options: Vec<CompilerOption> = argv.iter().map(|a| CompilerOption::new(a)).collect();
+
+    let mut visitors: Vec<Box<dyn OptionVisitor>> = vec![
+        Box::new(SanitizerVisitor::new()),
+        Box::new(CompilationDatabaseVisitor::new()),
+    ];
+    apply_visitors(&mut visitors, &mut options);
+
+    let args: Vec<String> = options.iter().map(|o| o.as_str().to_string()).collect();
+    // From here on, reports record the argv actually forwarded to the compiler.
+    let compiler_argv: Vec<String> = std::iter::once(compiler.clone()).chain(args.iter().cloned()).collect();
+    match Command::new(&compiler).args(&args).status() {
+        Ok(status) => match status.code() {
+            Some(0) => std::process::exit(0),
+            // A non-zero exit or a signal-kill (no code) means the compiler run
+            // went wrong: capture the wrapper's decision state before exiting.
+            Some(code) => {
+                ice::report(&compiler_argv);
+                std::process::exit(code);
+            }
+            None => {
+                ice::report(&compiler_argv);
+                std::process::exit(1);
+            }
+        },
+        Err(e) => {
+            ice::report(&compiler_argv);
+            eprintln!("argus: failed to run {}: {}", compiler, e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use std::{io, path::Path};
 
 use super::OptionVisitor;
-use crate::{compiler_option::CompilerOption, env::COMPILATION_DATABASE_DIR};
+use crate::{
+    compiler_option::CompilerOption, emitter::Diagnostic, env::COMPILATION_DATABASE_DIR,
+};
 use uuid::Uuid;
 
 pub struct CompilationDatabaseVisitor {
@@ -37,6 +39,10 @@ fn prepare_compilation_database_folder(dir: &str) -> io::Result<()> {
 }
 
 impl OptionVisitor for CompilationDatabaseVisitor {
+    fn name(&self) -> &'static str {
+        "CompilationDatabaseVisitor::visit"
+    }
+
     fn visit(&mut self, options: &mut Vec<CompilerOption>) {
         self.init();
         if let Some(dir) = &self.compilation_database_dir {
@@ -46,10 +52,14 @@ impl OptionVisitor for CompilationDatabaseVisitor {
                 options.push(CompilerOption::new("-MJ"));
                 options.push(CompilerOption::new(&format!("{}/{}.json", dir, uuid)));
             } else {
-                eprintln!(
+                Diagnostic::warning(format!(
                     "Failed to prepare compilation database folder: {}, ignoring",
                     dir
-                );
+                ))
+                .code("compdb-folder")
+                .env_var(COMPILATION_DATABASE_DIR)
+                .path(dir)
+                .emit();
             }
         }
     }
@@ -0,0 +1,21 @@
+use crate::compiler_option::CompilerOption;
+use crate::profiler::TimingGuard;
+
+pub mod compilation_database_visitor;
+pub mod sanitizer_visitor;
+
+/// A visitor that inspects and mutates the compiler option list.
+pub trait OptionVisitor {
+    /// Human-readable label for the visitor, used when profiling its phase.
+    fn name(&self) -> &'static str;
+    fn visit(&mut self, options: &mut Vec<CompilerOption>);
+}
+
+/// Runs every visitor over the shared option list, in order, timing each
+/// `visit` call so the profile covers the whole pipeline.
+pub fn apply_visitors(visitors: &mut [Box<dyn OptionVisitor>], options: &mut Vec<CompilerOption>) {
+    for visitor in visitors.iter_mut() {
+        let _timer = TimingGuard::start(visitor.name());
+        visitor.visit(options);
+    }
+}
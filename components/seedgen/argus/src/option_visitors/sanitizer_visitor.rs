@@ -0,0 +1,56 @@
+use super::OptionVisitor;
+use crate::compiler_option::CompilerOption;
+use crate::emitter::Diagnostic;
+use crate::sanitizer::SanitizerSet;
+
+pub struct SanitizerVisitor {
+    set: SanitizerSet,
+}
+
+impl Default for SanitizerVisitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SanitizerVisitor {
+    pub fn new() -> Self {
+        SanitizerVisitor {
+            set: SanitizerSet::from_env(),
+        }
+    }
+}
+
+impl OptionVisitor for SanitizerVisitor {
+    fn name(&self) -> &'static str {
+        "SanitizerVisitor::visit"
+    }
+
+    fn visit(&mut self, options: &mut Vec<CompilerOption>) {
+        // Validate before emitting any flags: abort on a conflicting pair rather
+        // than handing the backend a combination it cannot codegen.
+        if let Err(e) = self.set.validate() {
+            Diagnostic::error(e.to_string())
+                .code("sanitizer-conflict")
+                .emit();
+            std::process::exit(1);
+        }
+
+        let mut features = Vec::new();
+        if self.set.contains(SanitizerSet::ADDRESS) {
+            features.push("address");
+        }
+        if self.set.contains(SanitizerSet::MEMORY) {
+            features.push("memory");
+        }
+        if self.set.contains(SanitizerSet::UNDEFINED_BEHAVIOR) {
+            features.push("undefined");
+        }
+        if !features.is_empty() {
+            options.push(CompilerOption::new(&format!("-fsanitize={}", features.join(","))));
+        }
+        if self.set.contains(SanitizerSet::COVERAGE) {
+            options.push(CompilerOption::new("-fsanitize-coverage=trace-pc-guard"));
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+
+use crate::env::{ARGUS_DEBUG, PROFILING};
+
+/// A single completed timed event, kept in the Chrome `trace_event` shape so
+/// serialization is a direct field copy.
+#[derive(Debug, Clone)]
+struct Event {
+    name: String,
+    /// Offset from the profiler epoch, in microseconds.
+    ts: u128,
+    /// Duration of the event, in microseconds.
+    dur: u128,
+    tid: i64,
+}
+
+/// Per-thread event buffer. Appending is uncontended; the serializer only
+/// touches the buffers once, at finalize time.
+type ThreadBuffer = Arc<Mutex<Vec<Event>>>;
+
+/// Global self-profiler, loosely modelled on rustc's `SelfProfiler`.
+///
+/// Recording threads push into their own buffer and the buffers are drained in
+/// one pass when the process exits.
+struct Profiler {
+    epoch: Instant,
+    output: Option<String>,
+    buffers: Mutex<Vec<ThreadBuffer>>,
+}
+
+static PROFILER: OnceLock<Option<Profiler>> = OnceLock::new();
+
+thread_local! {
+    static THREAD_BUFFER: ThreadBuffer = register_thread_buffer();
+}
+
+/// Resolves the configured output path, or `None` when profiling is disabled.
+///
+/// A bare `1`/`true` selects the default `/tmp/argus-profile.json`.
+fn output_path() -> Option<String> {
+    match std::env::var(PROFILING) {
+        Ok(value) => match value.as_str() {
+            "" => None,
+            "1" | "true" => Some(String::from("/tmp/argus-profile.json")),
+            path => Some(path.to_string()),
+        },
+        Err(_) => None,
+    }
+}
+
+fn profiler() -> Option<&'static Profiler> {
+    PROFILER
+        .get_or_init(|| {
+            output_path().map(|output| {
+                // Drain and serialize the buffers once the process exits.
+                unsafe {
+                    libc::atexit(finalize_at_exit);
+                }
+                Profiler {
+                    epoch: Instant::now(),
+                    output: Some(output),
+                    buffers: Mutex::new(Vec::new()),
+                }
+            })
+        })
+        .as_ref()
+}
+
+/// `atexit` trampoline into [`finalize`].
+extern "C" fn finalize_at_exit() {
+    finalize();
+}
+
+fn register_thread_buffer() -> ThreadBuffer {
+    let buffer: ThreadBuffer = Arc::new(Mutex::new(Vec::new()));
+    if let Some(profiler) = profiler() {
+        if let Ok(mut buffers) = profiler.buffers.lock() {
+            buffers.push(Arc::clone(&buffer));
+        }
+    }
+    buffer
+}
+
+/// Gets the OS thread id the same way the callgraph runtime does.
+fn thread_id() -> i64 {
+    unsafe { libc::syscall(libc::SYS_gettid) }
+}
+
+/// Scoped timer: records an event covering its lifetime when dropped.
+///
+/// A no-op (holding `None`) when profiling is disabled, so callers can wrap
+/// every `OptionVisitor::visit` unconditionally.
+pub struct TimingGuard {
+    phase: Option<(&'static str, Instant)>,
+}
+
+impl TimingGuard {
+    /// Starts timing `phase`. Returns an inert guard if profiling is off.
+    pub fn start(phase: &'static str) -> Self {
+        let phase = profiler().map(|p| (phase, p.epoch));
+        TimingGuard {
+            phase: phase.map(|(name, _)| (name, Instant::now())),
+        }
+    }
+}
+
+impl Drop for TimingGuard {
+    fn drop(&mut self) {
+        let Some((name, start)) = self.phase else {
+            return;
+        };
+        let Some(profiler) = profiler() else {
+            return;
+        };
+
+        let event = Event {
+            name: name.to_string(),
+            ts: start.duration_since(profiler.epoch).as_micros(),
+            dur: start.elapsed().as_micros(),
+            tid: thread_id(),
+        };
+        THREAD_BUFFER.with(|buffer| {
+            if let Ok(mut buffer) = buffer.lock() {
+                buffer.push(event);
+            }
+        });
+    }
+}
+
+/// Formats a microsecond duration as a human-readable seconds string, e.g.
+/// `0.012s`, mirroring rustc's `duration_to_secs_str`.
+fn duration_to_secs_str(micros: u128) -> String {
+    format!("{:.3}s", micros as f64 / 1_000_000.0)
+}
+
+/// Drains every thread buffer and writes the Chrome `trace_event` JSON.
+///
+/// Call once on process exit. Prints a per-phase summary to stderr when
+/// `ARGUS_DEBUG` is set.
+pub fn finalize() {
+    let Some(profiler) = profiler() else {
+        return;
+    };
+    let Some(output) = profiler.output.as_ref() else {
+        return;
+    };
+
+    let events: Vec<Event> = {
+        let buffers = match profiler.buffers.lock() {
+            Ok(buffers) => buffers,
+            Err(_) => return,
+        };
+        buffers
+            .iter()
+            .filter_map(|buffer| buffer.lock().ok().map(|b| b.clone()))
+            .flatten()
+            .collect()
+    };
+
+    let pid = std::process::id();
+    let mut json = String::from("[");
+    for (i, event) in events.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        let _ = write!(
+            json,
+            "{{\"name\":\"{}\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"tid\":{},\"pid\":{}}}",
+            event.name, event.ts, event.dur, event.tid, pid
+        );
+    }
+    json.push(']');
+
+    if let Err(e) = fs::write(output, json) {
+        eprintln!("Failed to write profile to {}: {}", output, e);
+    }
+
+    if std::env::var(ARGUS_DEBUG).is_ok() {
+        for event in &events {
+            eprintln!("[profile] {}: {}", event.name, duration_to_secs_str(event.dur));
+        }
+    }
+}
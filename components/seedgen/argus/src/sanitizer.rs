@@ -0,0 +1,148 @@
+use std::fmt;
+
+use crate::env::{ENABLE_ASAN, ENABLE_COVSAN, ENABLE_MSAN, ENABLE_UBSAN, NOSANITIZER};
+
+/// The sanitizers Argus knows how to request from the wrapped compiler.
+///
+/// Modelled as a small bitflags-style set so the mutual-exclusion matrix can be
+/// expressed the same way rustc diagnoses incompatible `-Zsanitizer` choices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SanitizerSet {
+    bits: u8,
+}
+
+impl SanitizerSet {
+    pub const ADDRESS: SanitizerSet = SanitizerSet { bits: 1 << 0 };
+    pub const MEMORY: SanitizerSet = SanitizerSet { bits: 1 << 1 };
+    pub const COVERAGE: SanitizerSet = SanitizerSet { bits: 1 << 2 };
+    pub const UNDEFINED_BEHAVIOR: SanitizerSet = SanitizerSet { bits: 1 << 3 };
+
+    /// An empty set, no sanitizers requested.
+    pub const fn empty() -> Self {
+        SanitizerSet { bits: 0 }
+    }
+
+    pub const fn contains(self, other: SanitizerSet) -> bool {
+        (self.bits & other.bits) == other.bits
+    }
+
+    pub fn insert(&mut self, other: SanitizerSet) {
+        self.bits |= other.bits;
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.bits == 0
+    }
+
+    /// Builds the set from the sanitizer env vars.
+    ///
+    /// `BANDFUZZ_NOSAN` wins over everything else: when it is set the result is
+    /// the empty set regardless of the other flags.
+    pub fn from_env() -> Self {
+        if std::env::var(NOSANITIZER).is_ok() {
+            return SanitizerSet::empty();
+        }
+
+        let mut set = SanitizerSet::empty();
+        if std::env::var(ENABLE_ASAN).is_ok() {
+            set.insert(SanitizerSet::ADDRESS);
+        }
+        if std::env::var(ENABLE_MSAN).is_ok() {
+            set.insert(SanitizerSet::MEMORY);
+        }
+        if std::env::var(ENABLE_COVSAN).is_ok() {
+            set.insert(SanitizerSet::COVERAGE);
+        }
+        if std::env::var(ENABLE_UBSAN).is_ok() {
+            set.insert(SanitizerSet::UNDEFINED_BEHAVIOR);
+        }
+        set
+    }
+
+    /// Rejects sanitizer combinations the backend cannot codegen.
+    ///
+    /// Address and Memory are mutually exclusive; coverage and
+    /// UndefinedBehavior instrumentation compose with everything. An empty set
+    /// (e.g. after `BANDFUZZ_NOSAN`) is always valid, so validation
+    /// short-circuits there.
+    pub fn validate(self) -> Result<(), SanitizerError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        if self.contains(SanitizerSet::ADDRESS) && self.contains(SanitizerSet::MEMORY) {
+            return Err(SanitizerError::Incompatible {
+                first: ENABLE_ASAN,
+                second: ENABLE_MSAN,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Errors surfaced while resolving the requested sanitizers.
+#[derive(Debug)]
+pub enum SanitizerError {
+    Incompatible {
+        first: &'static str,
+        second: &'static str,
+    },
+}
+
+impl std::error::Error for SanitizerError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_set_validates() {
+        assert!(SanitizerSet::empty().validate().is_ok());
+    }
+
+    #[test]
+    fn compatible_combinations_validate() {
+        let mut set = SanitizerSet::ADDRESS;
+        set.insert(SanitizerSet::COVERAGE);
+        set.insert(SanitizerSet::UNDEFINED_BEHAVIOR);
+        assert!(set.validate().is_ok());
+    }
+
+    #[test]
+    fn address_and_memory_conflict() {
+        let mut set = SanitizerSet::ADDRESS;
+        set.insert(SanitizerSet::MEMORY);
+        match set.validate() {
+            Err(SanitizerError::Incompatible { first, second }) => {
+                assert_eq!(first, ENABLE_ASAN);
+                assert_eq!(second, ENABLE_MSAN);
+            }
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn nosan_short_circuits_from_env() {
+        // `BANDFUZZ_NOSAN` clears the set even when sanitizers are requested.
+        std::env::set_var(NOSANITIZER, "1");
+        std::env::set_var(ENABLE_ASAN, "1");
+        let set = SanitizerSet::from_env();
+        std::env::remove_var(NOSANITIZER);
+        std::env::remove_var(ENABLE_ASAN);
+
+        assert!(set.is_empty());
+    }
+}
+
+impl fmt::Display for SanitizerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SanitizerError::Incompatible { first, second } => write!(
+                f,
+                "incompatible sanitizers requested: {} cannot be combined with {}",
+                first, second
+            ),
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use std::env;
+use std::fmt::Write as _;
+
+use lazy_static::lazy_static;
+
+/// Severity of a [`Diagnostic`].
+#[derive(Debug, Clone, Copy)]
+pub enum Level {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Level {
+    fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Note => "note",
+        }
+    }
+}
+
+/// A structured diagnostic.
+///
+/// The free-form `message` is what the human backend prints; the optional
+/// context fields are what the JSON backend exposes under `spans` so a driving
+/// harness can key off them instead of scraping text.
+#[derive(Debug, Default)]
+pub struct Diagnostic {
+    level: Option<Level>,
+    message: String,
+    code: Option<&'static str>,
+    env_var: Option<String>,
+    path: Option<String>,
+    callee: Option<String>,
+    caller: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Level::Error, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Level::Warning, message)
+    }
+
+    pub fn note(message: impl Into<String>) -> Self {
+        Self::new(Level::Note, message)
+    }
+
+    fn new(level: Level, message: impl Into<String>) -> Self {
+        Diagnostic {
+            level: Some(level),
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    pub fn env_var(mut self, env_var: impl Into<String>) -> Self {
+        self.env_var = Some(env_var.into());
+        self
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn callee(mut self, callee: impl Into<String>) -> Self {
+        self.callee = Some(callee.into());
+        self
+    }
+
+    pub fn caller(mut self, caller: impl Into<String>) -> Self {
+        self.caller = Some(caller.into());
+        self
+    }
+
+    fn level(&self) -> Level {
+        self.level.unwrap_or(Level::Error)
+    }
+
+    /// Emits this diagnostic through the process-wide emitter.
+    pub fn emit(self) {
+        EMITTER.emit(&self);
+    }
+}
+
+/// Backend that renders a [`Diagnostic`].
+trait Emitter: Sync {
+    fn emit(&self, diag: &Diagnostic);
+}
+
+/// Reproduces today's free-form stderr output.
+struct HumanEmitter;
+
+impl Emitter for HumanEmitter {
+    fn emit(&self, diag: &Diagnostic) {
+        eprintln!("{}", diag.message);
+    }
+}
+
+/// Emits one JSON object per line: `{level, message, code, spans:{...}}`.
+struct JsonEmitter;
+
+impl Emitter for JsonEmitter {
+    fn emit(&self, diag: &Diagnostic) {
+        let mut spans = String::new();
+        let mut push = |key: &str, value: &str| {
+            if !spans.is_empty() {
+                spans.push(',');
+            }
+            let _ = write!(spans, "\"{}\":{}", key, json_string(value));
+        };
+        if let Some(env_var) = &diag.env_var {
+            push("env_var", env_var);
+        }
+        if let Some(path) = &diag.path {
+            push("path", path);
+        }
+        if let Some(callee) = &diag.callee {
+            push("callee", callee);
+        }
+        if let Some(caller) = &diag.caller {
+            push("caller", caller);
+        }
+
+        let code = diag
+            .code
+            .map(|c| json_string(c))
+            .unwrap_or_else(|| String::from("null"));
+        // Emit to stderr for parity with the human backend: this runtime is
+        // linked into the instrumented target, so stdout belongs to it.
+        eprintln!(
+            "{{\"level\":{},\"message\":{},\"code\":{},\"spans\":{{{}}}}}",
+            json_string(diag.level().as_str()),
+            json_string(&diag.message),
+            code,
+            spans
+        );
+    }
+}
+
+/// Escapes a string into a JSON string literal (quotes included).
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+lazy_static! {
+    static ref EMITTER: Box<dyn Emitter> = select_emitter();
+}
+
+/// Selects the backend from `CALLGRAPH_ERROR_FORMAT` (`json` for the
+/// machine-readable emitter, anything else for the human one).
+fn select_emitter() -> Box<dyn Emitter> {
+    match env::var("CALLGRAPH_ERROR_FORMAT").as_deref() {
+        Ok("json") => Box::new(JsonEmitter),
+        _ => Box::new(HumanEmitter),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::json_string;
+
+    #[test]
+    fn plain_string_is_quoted() {
+        assert_eq!(json_string("main"), "\"main\"");
+    }
+
+    #[test]
+    fn quotes_and_backslashes_are_escaped() {
+        assert_eq!(json_string("a\"b\\c"), "\"a\\\"b\\\\c\"");
+    }
+
+    #[test]
+    fn control_characters_are_escaped() {
+        assert_eq!(json_string("line\nnext\ttab"), "\"line\\nnext\\ttab\"");
+    }
+}
@@ -4,52 +4,111 @@ use std::{
     cell::Cell,
     env,
     fs::{File, OpenOptions},
-    io::Write,
+    io::{BufWriter, Write},
     os::raw::c_void,
     path::PathBuf,
-    sync::Mutex,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
 };
 
+mod emitter;
 mod error;
+use emitter::Diagnostic;
 use error::CallGraphError;
 
 type Result<T> = std::result::Result<T, CallGraphError>;
-type FunctionName = Option<String>;
-type FunctionPair = (FunctionName, FunctionName);
+
+/// Interned symbol id. Edges are stored as pairs of these so each buffered
+/// record is fixed-size.
+type SymbolId = u32;
+
+/// A single recorded edge, kept as fixed-size integers so the per-thread
+/// buffers never touch the heap beyond their backing `Vec`.
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    tid: i64,
+    callee: SymbolId,
+    caller: SymbolId,
+}
+
+/// Output format for the edge list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    /// `tid|callee|caller` lines.
+    Text,
+    /// Fixed-width little-endian records consumers can mmap.
+    Binary,
+}
+
+/// How often the background serializer drains the per-thread buffers.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
 
 /// Configuration for the call graph logging
 #[derive(Debug)]
 struct Config {
     enabled: bool,
     log_path: PathBuf,
+    format: OutputFormat,
 }
 
 impl Default for Config {
     fn default() -> Self {
+        // `EXPORT_CALLS=binary` selects the compact mmap-able edge list; any
+        // other value keeps the legacy text format.
+        let value = env::var("EXPORT_CALLS");
+        let format = match value.as_deref() {
+            Ok("binary") => OutputFormat::Binary,
+            _ => OutputFormat::Text,
+        };
         Self {
-            enabled: env::var("EXPORT_CALLS").is_ok(),
+            enabled: value.is_ok(),
             log_path: PathBuf::from("/tmp/callgraph.log"),
+            format,
         }
     }
 }
 
 lazy_static! {
     static ref CONFIG: Config = Config::default();
-    static ref LOG_FILE: Mutex<Option<File>> = Mutex::new(None);
-    static ref SEEN_PAIRS: DashSet<FunctionPair> = DashSet::new();
-    static ref SYMBOL_CACHE: DashMap<usize, FunctionName> = DashMap::new();
+    static ref LOG_FILE: Mutex<Option<BufWriter<File>>> = Mutex::new(None);
+    static ref SEEN_PAIRS: DashSet<(SymbolId, SymbolId)> = DashSet::new();
+    /// Call edges already reported as skipped, keyed by `(callee_pc, caller_pc)`
+    /// so the note fires at most once per distinct unresolved pair.
+    static ref SEEN_SKIPS: DashSet<(usize, usize)> = DashSet::new();
+    /// Cache from program counter to its interned symbol id (`None` when symbol
+    /// resolution failed for that pc).
+    static ref SYMBOL_CACHE: DashMap<usize, Option<SymbolId>> = DashMap::new();
+    /// Symbol interner: name -> id, plus the reverse table used when writing the
+    /// human-readable text format.
+    static ref SYMBOLS: DashMap<String, SymbolId> = DashMap::new();
+    static ref SYMBOL_TABLE: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    /// Registry of every thread's buffer so the serializer can drain them all.
+    static ref BUFFERS: Mutex<Vec<Arc<Mutex<Vec<Edge>>>>> = Mutex::new(Vec::new());
 }
 
-/// Thread-local storage to prevent recursion and track thread IDs
+/// Thread-local storage to prevent recursion, track thread IDs, and hold each
+/// thread's own edge buffer. The buffer sits behind a mutex, which the
+/// serializer also takes when it drains the buffer.
 mod thread_locals {
     use super::*;
 
     thread_local! {
         pub static PREVENT_RECURSION: Cell<bool> = const { Cell::new(false) };
         pub static THREAD_ID: Cell<i64> = const { Cell::new(-1) };
+        pub static BUFFER: Arc<Mutex<Vec<Edge>>> = register_thread_buffer();
     }
 }
 
+/// Allocates this thread's edge buffer and registers it with the serializer.
+fn register_thread_buffer() -> Arc<Mutex<Vec<Edge>>> {
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    if let Ok(mut buffers) = BUFFERS.lock() {
+        buffers.push(Arc::clone(&buffer));
+    }
+    buffer
+}
+
 /// RAII guard to prevent recursion
 struct RecursionGuard;
 
@@ -81,42 +140,70 @@ pub extern "C" fn __seedmind_record_func_call(caller: *mut c_void, callee: *mut
     let _guard = match RecursionGuard::new() {
         Some(guard) => guard,
         None => {
-            eprintln!("Recursion detected in __seedmind_record_func_call");
+            Diagnostic::warning("Recursion detected in __seedmind_record_func_call")
+                .code("recursion")
+                .emit();
             return;
         }
     };
 
     if let Err(e) = record_call(caller, callee) {
-        eprintln!("Error recording function call: {}", e);
+        Diagnostic::error(format!("Error recording function call: {}", e))
+            .code("record-call")
+            .emit();
     }
 }
 
-/// Records a single function call to the log file
+/// Records a single function call into this thread's buffer.
+///
+/// The edge is not written here: it is interned to fixed-size ids and appended
+/// to the thread-local buffer, which the background serializer drains. Only the
+/// first call opens the log file, and unresolved edges are noted on stderr.
 fn record_call(caller: *mut c_void, callee: *mut c_void) -> Result<()> {
+    // Nothing is interned or buffered when logging is off, so the per-thread
+    // buffers never grow in a binary that runs without `EXPORT_CALLS`.
+    if !CONFIG.enabled {
+        return Ok(());
+    }
+
     static INITIALIZED: std::sync::Once = std::sync::Once::new();
     INITIALIZED.call_once(|| {
         if CONFIG.enabled {
             if let Err(e) = initialize_logging() {
-                eprintln!("Failed to initialize logging: {}", e);
+                Diagnostic::error(format!("Failed to initialize logging: {}", e))
+                    .code("init-logging")
+                    .env_var("EXPORT_CALLS")
+                    .path(CONFIG.log_path.display().to_string())
+                    .emit();
             }
         }
     });
 
     let tid = get_thread_id();
-    let callee = symbolize_pc(callee);
-    let caller = symbolize_pc(caller);
+    let callee_id = symbolize_pc(callee);
+    let caller_id = symbolize_pc(caller);
 
-    // Skip if either symbol resolution failed
-    let (Some(callee_name), Some(caller_name)) = (callee.as_ref(), caller.as_ref()) else {
+    // Skip if either symbol resolution failed, noting the skip once per distinct
+    // pc pair so a harness can see which edge was dropped and why.
+    let (Some(callee_id), Some(caller_id)) = (callee_id, caller_id) else {
+        note_symbol_skip(callee as usize, caller as usize, callee_id, caller_id);
         return Ok(());
     };
 
-    let pair = (callee.clone(), caller.clone());
-    if !SEEN_PAIRS.insert(pair) {
+    if !SEEN_PAIRS.insert((callee_id, caller_id)) {
         return Ok(());
     }
 
-    write_log_entry(tid, callee_name, caller_name)
+    thread_locals::BUFFER.with(|buffer| {
+        if let Ok(mut buffer) = buffer.lock() {
+            buffer.push(Edge {
+                tid,
+                callee: callee_id,
+                caller: caller_id,
+            });
+        }
+    });
+    Ok(())
 }
 
 /// Gets or initializes the thread ID
@@ -133,27 +220,61 @@ fn get_thread_id() -> i64 {
     })
 }
 
-/// Writes a single entry to the log file
-fn write_log_entry(tid: i64, callee: &str, caller: &str) -> Result<()> {
-    let mut file_guard = LOG_FILE.lock().map_err(|_| CallGraphError::LockError)?;
-    let file = file_guard.as_mut().ok_or(CallGraphError::NoLogFile)?;
+/// Resolves an interned id to its name for diagnostics, falling back to a
+/// placeholder when the symbol could not be resolved.
+fn symbol_label(id: Option<SymbolId>) -> String {
+    match id {
+        Some(id) => SYMBOL_TABLE
+            .lock()
+            .ok()
+            .and_then(|table| table.get(id as usize).cloned())
+            .unwrap_or_else(|| String::from("<unknown>")),
+        None => String::from("<unresolved>"),
+    }
+}
 
-    writeln!(file, "{:?}|{}|{}", tid, callee, caller)?;
-    file.sync_all()?;
-    Ok(())
+/// Emits a one-time note describing a dropped call edge whose callee or caller
+/// could not be symbolized.
+fn note_symbol_skip(callee_pc: usize, caller_pc: usize, callee: Option<SymbolId>, caller: Option<SymbolId>) {
+    if !SEEN_SKIPS.insert((callee_pc, caller_pc)) {
+        return;
+    }
+    Diagnostic::note("Skipping call edge with unresolved symbol")
+        .code("symbol-skip")
+        .callee(symbol_label(callee))
+        .caller(symbol_label(caller))
+        .emit();
 }
 
-/// Resolves a program counter to a function name, using cache
+/// Interns a symbol name, returning its stable id.
+fn intern_symbol(name: String) -> SymbolId {
+    if let Some(id) = SYMBOLS.get(&name) {
+        return *id;
+    }
+    // Allocate the id under the table lock and push in the same critical
+    // section, so ids and table indices can never diverge even when two threads
+    // first-see the same symbol concurrently.
+    let mut table = SYMBOL_TABLE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(id) = SYMBOLS.get(&name) {
+        return *id;
+    }
+    let id = table.len() as SymbolId;
+    table.push(name.clone());
+    SYMBOLS.insert(name, id);
+    id
+}
+
+/// Resolves a program counter to an interned symbol id, using the pc cache.
 #[inline]
-fn symbolize_pc(pc: *mut c_void) -> Option<String> {
+fn symbolize_pc(pc: *mut c_void) -> Option<SymbolId> {
     let pc_usize = pc as usize;
-    if let Some(cache) = SYMBOL_CACHE.get(&pc_usize) {
-        return cache.clone();
+    if let Some(cached) = SYMBOL_CACHE.get(&pc_usize) {
+        return *cached;
     }
 
-    let symbol = resolve_symbol(pc);
-    SYMBOL_CACHE.insert(pc_usize, symbol.clone());
-    symbol
+    let id = resolve_symbol(pc).map(intern_symbol);
+    SYMBOL_CACHE.insert(pc_usize, id);
+    id
 }
 
 /// Actually performs the symbol resolution
@@ -177,7 +298,105 @@ fn resolve_symbol(pc: *mut c_void) -> Option<String> {
     function_name
 }
 
-/// Initializes the log file
+/// Drains every thread buffer and writes the accumulated edges in one batch,
+/// syncing exactly once.
+fn flush() -> Result<()> {
+    let batch: Vec<Edge> = {
+        let buffers = BUFFERS.lock().map_err(|_| CallGraphError::LockError)?;
+        buffers
+            .iter()
+            .filter_map(|buffer| buffer.lock().ok().map(|mut b| std::mem::take(&mut *b)))
+            .flatten()
+            .collect()
+    };
+    if batch.is_empty() {
+        return Ok(());
+    }
+
+    let mut file_guard = LOG_FILE.lock().map_err(|_| CallGraphError::LockError)?;
+    let file = file_guard.as_mut().ok_or(CallGraphError::NoLogFile)?;
+
+    match CONFIG.format {
+        OutputFormat::Text => {
+            let table = SYMBOL_TABLE.lock().map_err(|_| CallGraphError::LockError)?;
+            for edge in &batch {
+                let callee = table.get(edge.callee as usize).map(String::as_str).unwrap_or("?");
+                let caller = table.get(edge.caller as usize).map(String::as_str).unwrap_or("?");
+                writeln!(file, "{:?}|{}|{}", edge.tid, callee, caller)?;
+            }
+        }
+        OutputFormat::Binary => {
+            for edge in &batch {
+                file.write_all(&edge.tid.to_le_bytes())?;
+                file.write_all(&edge.callee.to_le_bytes())?;
+                file.write_all(&edge.caller.to_le_bytes())?;
+            }
+        }
+    }
+
+    file.flush()?;
+    file.get_ref().sync_all()?;
+    Ok(())
+}
+
+/// Writes the interned symbol table to `<log_path>.symbols` as `id|name` lines,
+/// in id order, so consumers of the binary edge list can resolve ids.
+///
+/// Called once at finalize rather than per flush: the table only grows by
+/// appends, so rewriting it on every tick would be pure churn.
+fn write_symbol_table() -> Result<()> {
+    let table = SYMBOL_TABLE.lock().map_err(|_| CallGraphError::LockError)?;
+    let path = CONFIG.log_path.with_extension("symbols");
+    let mut file = BufWriter::new(File::create(&path)?);
+    for (id, name) in table.iter().enumerate() {
+        writeln!(file, "{}|{}", id, name)?;
+    }
+    file.flush()?;
+    file.get_ref().sync_all()?;
+    Ok(())
+}
+
+/// Spawns the background serializer that periodically drains the buffers.
+fn spawn_serializer() {
+    thread::Builder::new()
+        .name("callgraph-serializer".into())
+        .spawn(|| {
+            // The serializer must never record its own edges.
+            thread_locals::PREVENT_RECURSION.set(true);
+            loop {
+                thread::sleep(FLUSH_INTERVAL);
+                if let Err(e) = flush() {
+                    Diagnostic::error(format!("Failed to flush call graph: {}", e))
+                        .code("flush")
+                        .emit();
+                }
+            }
+        })
+        .ok();
+}
+
+/// `atexit` finalizer that flushes any edges still buffered at process exit,
+/// so the last batch is never lost between serializer ticks.
+extern "C" fn finalize() {
+    thread_locals::PREVENT_RECURSION.set(true);
+    if let Err(e) = flush() {
+        Diagnostic::error(format!("Failed to flush call graph on exit: {}", e))
+            .code("flush")
+            .emit();
+    }
+
+    // Emit the id -> name sidecar once, now that the table has stopped growing,
+    // so the binary edge list is self-describing.
+    if CONFIG.format == OutputFormat::Binary {
+        if let Err(e) = write_symbol_table() {
+            Diagnostic::error(format!("Failed to write symbol table: {}", e))
+                .code("symbol-table")
+                .emit();
+        }
+    }
+}
+
+/// Initializes the log file, background serializer, and exit finalizer.
 fn initialize_logging() -> Result<()> {
     let file = OpenOptions::new()
         .write(true)
@@ -185,6 +404,10 @@ fn initialize_logging() -> Result<()> {
         .truncate(true)
         .open(&CONFIG.log_path)?;
 
-    *LOG_FILE.lock().map_err(|_| CallGraphError::LockError)? = Some(file);
+    *LOG_FILE.lock().map_err(|_| CallGraphError::LockError)? = Some(BufWriter::new(file));
+    unsafe {
+        libc::atexit(finalize);
+    }
+    spawn_serializer();
     Ok(())
 }